@@ -0,0 +1,124 @@
+//! Bit level parsers and combinators
+//!
+//! Bit-packed formats (flag fields, length prefixes whose fields straddle
+//! byte boundaries) don't fit `take!`, which only ever consumes whole
+//! bytes. `bits!` adapts a normal `&[u8]` parser into one that runs over an
+//! `(&[u8], usize)` cursor, the `usize` being the bit offset into the
+//! current byte; `take_bits!` is the bit-level equivalent of `take!`.
+
+/// `bits!( parser ) => &[u8] -> IResult<&[u8], O>`
+/// transforms its byte slice input into a `(&[u8], usize)` input for the
+/// underlying bit-level parsers, and translates back afterwards
+///
+/// the second element of the cursor is the bit index in the first byte,
+/// counting from the most significant bit; `bits!` consumes whole input
+/// bytes only, realigning to the next byte boundary once the wrapped
+/// parser is done
+///
+/// ```
+/// # #[macro_use] extern crate nom;
+/// # use nom::IResult::Done;
+/// # fn main() {
+///  named!( nibbles<(u8,u8)>, bits!( tuple!( take_bits!(u8, 4), take_bits!(u8, 4) ) ) );
+///
+///  let r = nibbles(&[0xAB, 0xCD]);
+///  assert_eq!(r, Done(&[0xCD][..], (0xA, 0xB)));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! bits (
+  ($i:expr, $submac:ident!( $($args:tt)* )) => (
+    {
+      fn byte_err<'a,E>(e: $crate::Err<(&'a [u8], usize), E>) -> $crate::Err<&'a [u8], E> {
+        match e {
+          $crate::Err::Code(k)                    => $crate::Err::Code(k),
+          $crate::Err::Node(k, n)                 => $crate::Err::Node(k, Box::new(byte_err(*n))),
+          $crate::Err::Position(k, (i, _))        => $crate::Err::Position(k, i),
+          $crate::Err::NodePosition(k, (i, _), n) => $crate::Err::NodePosition(k, i, Box::new(byte_err(*n)))
+        }
+      }
+
+      match $submac!(($i, 0usize), $($args)*) {
+        $crate::IResult::Error(e)      => $crate::IResult::Error(byte_err(e)),
+        $crate::IResult::Incomplete(n) => $crate::IResult::Incomplete(n),
+        $crate::IResult::Done((rest, offset), o) => {
+          let byte_index = if offset == 0 { 0 } else { 1 };
+          $crate::IResult::Done(&rest[byte_index..], o)
+        }
+      }
+    }
+  );
+  ($i:expr, $f:expr) => (
+    bits!($i, call!($f));
+  );
+);
+
+/// `take_bits!(type, count) => (&[u8], usize) -> IResult<(&[u8], usize), type>`
+/// generates a parser consuming `count` bits and returning them assembled
+/// into `type`, most significant bit first
+#[macro_export]
+macro_rules! take_bits (
+  ($i:expr, $t:ty, $count:expr) => (
+    {
+      let (input, bit_offset): (&[u8], usize) = $i;
+      let count = $count as usize;
+      let total_bits = input.len() * 8 - bit_offset;
+
+      if count > total_bits {
+        let extra_bytes = (count - total_bits + 7) / 8;
+        $crate::IResult::Incomplete($crate::Needed::Size(input.len() + extra_bytes))
+      } else {
+        let mut acc: $t     = 0;
+        let mut offset      = bit_offset;
+        let mut byte_index  = 0;
+
+        for _ in 0..count {
+          let byte = input[byte_index];
+          let bit  = (byte >> (7 - offset)) & 0x1;
+
+          acc = (acc << 1) + (bit as $t);
+
+          offset += 1;
+          if offset == 8 {
+            offset = 0;
+            byte_index += 1;
+          }
+        }
+
+        let rest = &input[byte_index..];
+
+        $crate::IResult::Done((rest, offset), acc)
+      }
+    }
+  );
+);
+
+#[cfg(test)]
+mod tests {
+  use internal::Needed;
+  use internal::IResult::*;
+
+  #[test]
+  fn take_bits_nibbles() {
+    named!(nibbles<(u8,u8)>, bits!( tuple!( take_bits!(u8, 4), take_bits!(u8, 4) ) ) );
+
+    let input = [0xAB, 0xCD];
+    assert_eq!(nibbles(&input), Done(&[0xCD][..], (0xA, 0xB)));
+  }
+
+  #[test]
+  fn take_bits_realigns_to_next_byte() {
+    named!(six_bits<u8>, bits!( take_bits!(u8, 6) ) );
+
+    let input = [0b1010_1100];
+    assert_eq!(six_bits(&input), Done(&[][..], 0b0010_1011));
+  }
+
+  #[test]
+  fn take_bits_incomplete() {
+    named!(too_wide<u16>, bits!( take_bits!(u16, 9) ) );
+
+    let input = [0xFF];
+    assert_eq!(too_wide(&input), Incomplete(Needed::Size(2)));
+  }
+}
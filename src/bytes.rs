@@ -1,5 +1,11 @@
 //! Byte level parsers and combinators
 //!
+//! `tag!`, `take!` and the `take_until*` family are written against the
+//! `InputLength`/`Slice`/`Compare` traits from the `traits` module (the
+//! `take_until_either*` pair uses `FindToken` in place of `Compare`) instead
+//! of indexing `&[u8]` directly, so they also run unchanged over any other
+//! slice-like input (a `&[Token]` coming out of a lexer, for instance) that
+//! implements those traits.
 
 /// `tag!(&[T]: nom::AsBytes) => &[T] -> IResult<&[T], &[T]>`
 /// declares a byte array as a suite to recognize
@@ -19,20 +25,21 @@
 macro_rules! tag (
   ($i:expr, $inp: expr) => (
     {
-      #[inline(always)]
-      fn as_bytes<T: $crate::AsBytes>(b: &T) -> &[u8] {
-        b.as_bytes()
-      }
+      use $crate::{Compare,CompareResult,InputLength,Slice};
 
       let expected = $inp;
-      let bytes = as_bytes(&expected);
-
-      if bytes.len() > $i.len() {
-        $crate::IResult::Incomplete($crate::Needed::Size(bytes.len()))
-      } else if &$i[0..bytes.len()] == bytes {
-        $crate::IResult::Done(&$i[bytes.len()..], &$i[0..bytes.len()])
-      } else {
-        $crate::IResult::Error($crate::Err::Position($crate::ErrorKind::Tag, $i))
+      let blen     = expected.input_len();
+
+      match ($i).compare(expected) {
+        CompareResult::Ok => {
+          $crate::IResult::Done($i.slice(blen..), $i.slice(..blen))
+        },
+        CompareResult::Incomplete => {
+          $crate::IResult::Incomplete($crate::Needed::Size(blen))
+        },
+        CompareResult::Error => {
+          $crate::IResult::Error($crate::Err::Position($crate::ErrorKind::Tag, $i))
+        }
       }
     }
   );
@@ -196,11 +203,13 @@ macro_rules! filter(
 macro_rules! take(
   ($i:expr, $count:expr) => (
     {
+      use $crate::{InputLength,Slice};
+
       let cnt = $count as usize;
-      if $i.len() < cnt {
+      if $i.input_len() < cnt {
         $crate::IResult::Incomplete($crate::Needed::Size(cnt))
       } else {
-        $crate::IResult::Done(&$i[cnt..],&$i[0..cnt])
+        $crate::IResult::Done($i.slice(cnt..), $i.slice(..cnt))
       }
     }
   );
@@ -219,25 +228,24 @@ macro_rules! take_str (
 macro_rules! take_until_and_consume(
   ($i:expr, $inp:expr) => (
     {
-      #[inline(always)]
-      fn as_bytes<T: $crate::AsBytes>(b: &T) -> &[u8] {
-        b.as_bytes()
-      }
+      use $crate::{Compare,CompareResult,InputLength,Slice};
 
-      let expected   = $inp;
-      let bytes      = as_bytes(&expected);
-      if bytes.len() > $i.len() {
-        $crate::IResult::Incomplete($crate::Needed::Size(bytes.len()))
+      let expected = $inp;
+      let blen     = expected.input_len();
+      let len      = $i.input_len();
+
+      if blen > len {
+        $crate::IResult::Incomplete($crate::Needed::Size(blen))
       } else {
         let mut index  = 0;
         let mut parsed = false;
 
-        for idx in 0..$i.len() {
-          if idx + bytes.len() > $i.len() {
+        for idx in 0..len {
+          if idx + blen > len {
             index = idx;
             break;
           }
-          if &$i[idx..idx + bytes.len()] == bytes {
+          if let CompareResult::Ok = $i.slice(idx..idx + blen).compare(expected) {
             parsed = true;
             index = idx;
             break;
@@ -245,7 +253,7 @@ macro_rules! take_until_and_consume(
         }
 
         if parsed {
-          $crate::IResult::Done(&$i[(index + bytes.len())..], &$i[0..index])
+          $crate::IResult::Done($i.slice((index + blen)..), $i.slice(..index))
         } else {
           $crate::IResult::Error($crate::Err::Position($crate::ErrorKind::TakeUntilAndConsume,$i))
         }
@@ -260,25 +268,24 @@ macro_rules! take_until_and_consume(
 macro_rules! take_until(
   ($i:expr, $inp:expr) => (
     {
-      #[inline(always)]
-      fn as_bytes<T: $crate::AsBytes>(b: &T) -> &[u8] {
-        b.as_bytes()
-      }
+      use $crate::{Compare,CompareResult,InputLength,Slice};
 
-      let expected   = $inp;
-      let bytes      = as_bytes(&expected);
-      if bytes.len() > $i.len() {
-        $crate::IResult::Incomplete($crate::Needed::Size(bytes.len()))
+      let expected = $inp;
+      let blen     = expected.input_len();
+      let len      = $i.input_len();
+
+      if blen > len {
+        $crate::IResult::Incomplete($crate::Needed::Size(blen))
       } else {
         let mut index  = 0;
         let mut parsed = false;
 
-        for idx in 0..$i.len() {
-          if idx + bytes.len() > $i.len() {
+        for idx in 0..len {
+          if idx + blen > len {
             index = idx;
             break;
           }
-          if &$i[idx..idx+bytes.len()] == bytes {
+          if let CompareResult::Ok = $i.slice(idx..idx + blen).compare(expected) {
             parsed = true;
             index  = idx;
             break;
@@ -286,7 +293,7 @@ macro_rules! take_until(
         }
 
         if parsed {
-          $crate::IResult::Done(&$i[index..], &$i[0..index])
+          $crate::IResult::Done($i.slice(index..), $i.slice(..index))
         } else {
           $crate::IResult::Error($crate::Err::Position($crate::ErrorKind::TakeUntil,$i))
         }
@@ -301,36 +308,27 @@ macro_rules! take_until(
 macro_rules! take_until_either_and_consume(
   ($i:expr, $inp:expr) => (
     {
-      #[inline(always)]
-      fn as_bytes<T: $crate::AsBytes>(b: &T) -> &[u8] {
-        b.as_bytes()
-      }
+      use $crate::{FindToken,InputLength,Slice};
 
-      let expected   = $inp;
-      let bytes      = as_bytes(&expected);
-      if 1 > $i.len() {
+      let expected = $inp;
+      let len      = $i.input_len();
+
+      if 1 > len {
         $crate::IResult::Incomplete($crate::Needed::Size(1))
       } else {
         let mut index  = 0;
         let mut parsed = false;
 
-        for idx in 0..$i.len() {
-          if idx + 1 > $i.len() {
+        for idx in 0..len {
+          if expected.find_token($i[idx]) {
+            parsed = true;
             index = idx;
             break;
           }
-          for &t in bytes.iter() {
-            if $i[idx] == t {
-              parsed = true;
-              index = idx;
-              break;
-            }
-          }
-          if parsed { break; }
         }
 
         if parsed {
-          $crate::IResult::Done(&$i[(index+1)..], &$i[0..index])
+          $crate::IResult::Done($i.slice((index+1)..), $i.slice(..index))
         } else {
           $crate::IResult::Error($crate::Err::Position($crate::ErrorKind::TakeUntilEitherAndConsume,$i))
         }
@@ -344,36 +342,27 @@ macro_rules! take_until_either_and_consume(
 macro_rules! take_until_either(
   ($i:expr, $inp:expr) => (
     {
-      #[inline(always)]
-      fn as_bytes<T: $crate::AsBytes>(b: &T) -> &[u8] {
-        b.as_bytes()
-      }
+      use $crate::{FindToken,InputLength,Slice};
 
-      let expected   = $inp;
-      let bytes      = as_bytes(&expected);
-      if 1 > $i.len() {
+      let expected = $inp;
+      let len      = $i.input_len();
+
+      if 1 > len {
         $crate::IResult::Incomplete($crate::Needed::Size(1))
       } else {
         let mut index  = 0;
         let mut parsed = false;
 
-        for idx in 0..$i.len() {
-          if idx + 1 > $i.len() {
+        for idx in 0..len {
+          if expected.find_token($i[idx]) {
+            parsed = true;
             index = idx;
             break;
           }
-          for &t in bytes.iter() {
-            if $i[idx] == t {
-              parsed = true;
-              index = idx;
-              break;
-            }
-          }
-          if parsed { break; }
         }
 
         if parsed {
-          $crate::IResult::Done(&$i[index..], &$i[0..index])
+          $crate::IResult::Done($i.slice(index..), $i.slice(..index))
         } else {
           $crate::IResult::Error($crate::Err::Position($crate::ErrorKind::TakeUntilEither,$i))
         }
@@ -0,0 +1,79 @@
+//! Combinators for attaching a custom error payload to a parser's failure
+//!
+//! `ErrorKind::Custom(E)` on its own carries no information about *where*
+//! things went wrong. `error!`/`add_error!` wrap the `Err` of a failing
+//! parser in `Err::Node(ErrorKind::Custom(code), Box<inner>)`, keeping the
+//! original failure reachable as the boxed child, so a caller gets a
+//! walkable, typed error stack instead of an opaque code.
+
+/// `error!(code, parser) => I -> IResult<I, O, E>`
+/// wraps the `Err` of the wrapped parser in
+/// `Err::Node(ErrorKind::Custom(code), Box<inner>)` when it fails
+///
+/// ```
+/// # #[macro_use] extern crate nom;
+/// # use nom::IResult::Error;
+/// # use nom::Err::{Node,Position};
+/// # use nom::ErrorKind;
+/// # fn main() {
+///  named!(x<&[u8], &[u8], u32>, error!(42, tag!("abcd")));
+///
+///  let r = x(&b"efgh"[..]);
+///  assert_eq!(r, Error(Node(ErrorKind::Custom(42), Box::new(Position(ErrorKind::Tag, &b"efgh"[..])))));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! error (
+  ($i:expr, $code:expr, $submac:ident!( $($args:tt)* )) => (
+    {
+      match $submac!($i, $($args)*) {
+        $crate::IResult::Done(i,o)     => $crate::IResult::Done(i,o),
+        $crate::IResult::Incomplete(n) => $crate::IResult::Incomplete(n),
+        $crate::IResult::Error(e)      => {
+          $crate::IResult::Error($crate::Err::Node($crate::ErrorKind::Custom($code), Box::new(e)))
+        }
+      }
+    }
+  );
+  ($i:expr, $code:expr, $f:expr) => (
+    error!($i, $code, call!($f));
+  );
+);
+
+/// `add_error!(code, parser) => I -> IResult<I, O, E>`
+/// alias for `error!`, kept for call sites that read better as "add an
+/// error onto this parser" rather than "wrap this parser's error"
+#[macro_export]
+macro_rules! add_error (
+  ($i:expr, $code:expr, $submac:ident!( $($args:tt)* )) => (
+    error!($i, $code, $submac!($($args)*));
+  );
+  ($i:expr, $code:expr, $f:expr) => (
+    error!($i, $code, call!($f));
+  );
+);
+
+#[cfg(test)]
+mod tests {
+  use internal::IResult::*;
+  use internal::Err::*;
+  use util::ErrorKind;
+
+  #[test]
+  fn error_wraps_inner_failure() {
+    named!(x<&[u8], &[u8], u32>, error!(42, tag!("abcd")));
+
+    let r = x(&b"efgh"[..]);
+    assert_eq!(r, Error(Node(ErrorKind::Custom(42), Box::new(Position(ErrorKind::Tag, &b"efgh"[..])))));
+    assert_eq!(r.custom_error(), Some(&42));
+  }
+
+  #[test]
+  fn error_passes_through_success() {
+    named!(x<&[u8], &[u8], u32>, error!(42, tag!("abcd")));
+
+    let r = x(&b"abcdefgh"[..]);
+    assert_eq!(r, Done(&b"efgh"[..], &b"abcd"[..]));
+    assert_eq!(r.custom_error(), None);
+  }
+}
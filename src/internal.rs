@@ -35,12 +35,28 @@ impl<'a,I:Eq,O:Eq> Eq for IResultClosure<'a,I,O> {}
 //type IResultClosure<'a,I,O> = |I|:'a -> IResult<'a,I,O>;
 //type IResultClosure<'a,I,O> = Fn<I, IResult<'a,I,O>>;
 
+/// `I` is the input type carried along in `Position`/`NodePosition`, so the
+/// failing slice can be anything a parser runs over, not just `&[u8]`
 #[derive(Debug,PartialEq,Eq,Clone)]
-pub enum Err<'a,E=u32>{
+pub enum Err<I,E=u32>{
   Code(ErrorKind<E>),
-  Node(ErrorKind<E>, Box<Err<'a,E>>),
-  Position(ErrorKind<E>, &'a [u8]),
-  NodePosition(ErrorKind<E>, &'a [u8], Box<Err<'a,E>>)
+  Node(ErrorKind<E>, Box<Err<I,E>>),
+  Position(ErrorKind<E>, I),
+  NodePosition(ErrorKind<E>, I, Box<Err<I,E>>)
+}
+
+impl<I,E> Err<I,E> {
+  /// returns the `E` carried by the outermost `ErrorKind::Custom`, added by
+  /// `error!`/`add_error!`, if this error's outermost variant has one
+  pub fn custom_error(&self) -> Option<&E> {
+    match *self {
+      Err::Code(ErrorKind::Custom(ref e))               => Some(e),
+      Err::Node(ErrorKind::Custom(ref e), _)            => Some(e),
+      Err::Position(ErrorKind::Custom(ref e), _)        => Some(e),
+      Err::NodePosition(ErrorKind::Custom(ref e), _, _) => Some(e),
+      _                                                 => None
+    }
+  }
 }
 
 
@@ -63,9 +79,9 @@ pub enum Needed {
 /// * Incomplete will hold the closure used to restart the computation once more data is available.
 /// Current attemps at implementation of Incomplete are progressing, but slowed down by lifetime problems
 #[derive(Debug,PartialEq,Eq,Clone)]
-pub enum IResult<'a,I,O,E=u32> {
+pub enum IResult<I,O,E=u32> {
   Done(I,O),
-  Error(Err<'a,E>),
+  Error(Err<I,E>),
   //Incomplete(proc(I):'a -> IResult<I,O>)
   Incomplete(Needed)
   //Incomplete(Box<FnMut(I) -> IResult<I,O>>)
@@ -74,7 +90,7 @@ pub enum IResult<'a,I,O,E=u32> {
   //Incomplete(fn(I) -> IResult<'a,I,O>)
 }
 
-impl<'a,I,O> IResult<'a,I,O> {
+impl<I,O> IResult<I,O> {
   pub fn is_done(&self) -> bool {
     match self {
       &Done(_,_) => true,
@@ -97,6 +113,18 @@ impl<'a,I,O> IResult<'a,I,O> {
   }
 }
 
+impl<I,O,E> IResult<I,O,E> {
+  /// returns the `E` of the outermost `Err` of this result when its kind is
+  /// `ErrorKind::Custom` (as attached by `error!`/`add_error!`), if any;
+  /// nested errors are not searched
+  pub fn custom_error(&self) -> Option<&E> {
+    match self {
+      &Error(ref e) => e.custom_error(),
+      _             => None
+    }
+  }
+}
+
 pub trait GetInput<I> {
   fn remaining_input(&self) -> Option<I>;
 }
@@ -105,7 +133,7 @@ pub trait GetOutput<O> {
   fn output(&self) -> Option<O>;
 }
 
-impl<'a,I,O> GetInput<&'a[I]> for IResult<'a,&'a[I],O> {
+impl<'a,I,O> GetInput<&'a[I]> for IResult<&'a[I],O> {
   fn remaining_input(&self) -> Option<&'a[I]> {
     match self {
       &Done(ref i,_) => Some(*i),
@@ -114,7 +142,7 @@ impl<'a,I,O> GetInput<&'a[I]> for IResult<'a,&'a[I],O> {
   }
 }
 
-impl<'a,O> GetInput<()> for IResult<'a,(),O> {
+impl<O> GetInput<()> for IResult<(),O> {
   fn remaining_input(&self) -> Option<()> {
     match self {
       &Done((),_) => Some(()),
@@ -123,7 +151,7 @@ impl<'a,O> GetInput<()> for IResult<'a,(),O> {
   }
 }
 
-impl<'a,I,O> GetOutput<&'a[O]> for IResult<'a,I,&'a[O]> {
+impl<'a,I,O> GetOutput<&'a[O]> for IResult<I,&'a[O]> {
   fn output(&self) -> Option<&'a[O]> {
     match self {
       &Done(_, ref o) => Some(*o),
@@ -132,7 +160,7 @@ impl<'a,I,O> GetOutput<&'a[O]> for IResult<'a,I,&'a[O]> {
   }
 }
 
-impl<'a,I> GetOutput<()> for IResult<'a,I,()> {
+impl<I> GetOutput<()> for IResult<I,()> {
   fn output(&self) -> Option<()> {
     match self {
       &Done(_,()) => Some(()),
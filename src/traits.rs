@@ -0,0 +1,118 @@
+//! Traits for abstracting over input types
+//!
+//! `tag!`, `take!` and the `take_until*` family are written in terms of
+//! these instead of indexing `&[u8]` directly, so a parser built with
+//! `named!` can run over any slice-like input (a pre-lexed `&[Token]`,
+//! for instance) and not just bytes.
+
+#[cfg(feature = "core")]
+use std::prelude::v1::*;
+use std::ops::{Range,RangeFrom,RangeFull,RangeTo};
+
+/// Returns the length of the input, regardless of what it wraps
+pub trait InputLength {
+  fn input_len(&self) -> usize;
+}
+
+impl<'a,T> InputLength for &'a [T] {
+  #[inline(always)]
+  fn input_len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<'a> InputLength for &'a str {
+  #[inline(always)]
+  fn input_len(&self) -> usize {
+    self.len()
+  }
+}
+
+/// Slices the input given a range, the way `Index` would for a `[T]`
+///
+/// A trait instead of relying on `Index` directly so combinators can stay
+/// generic over anything that behaves like a slice
+pub trait Slice<R> {
+  fn slice(&self, range: R) -> Self;
+}
+
+macro_rules! impl_slice_range(
+  ($range:ty) => (
+    impl<'a,T> Slice<$range> for &'a [T] {
+      #[inline(always)]
+      fn slice(&self, range: $range) -> Self {
+        &self[range]
+      }
+    }
+  );
+);
+
+impl_slice_range!(Range<usize>);
+impl_slice_range!(RangeTo<usize>);
+impl_slice_range!(RangeFrom<usize>);
+impl_slice_range!(RangeFull);
+
+/// Result of comparing an input's prefix against an expected value
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum CompareResult {
+  Ok,
+  Incomplete,
+  Error
+}
+
+/// Used by `tag!`, `take_until!` and `take_until_and_consume!` to test an input's prefix
+/// against an expected value, without assuming the element type is `u8`
+///
+/// Implement this for a custom input type (e.g. a `&[Token]` coming out of
+/// a lexer) to reuse `tag!`/`take_until!` unchanged over it.
+pub trait Compare<T> {
+  fn compare(&self, t: T) -> CompareResult;
+}
+
+impl<'a,'b> Compare<&'b [u8]> for &'a [u8] {
+  #[inline(always)]
+  fn compare(&self, t: &'b [u8]) -> CompareResult {
+    let pos = self.iter().zip(t.iter()).position(|(a,b)| a != b);
+
+    match pos {
+      Some(_) => CompareResult::Error,
+      None    => if self.len() >= t.len() {
+        CompareResult::Ok
+      } else {
+        CompareResult::Incomplete
+      }
+    }
+  }
+}
+
+impl<'a,'b> Compare<&'b str> for &'a [u8] {
+  #[inline(always)]
+  fn compare(&self, t: &'b str) -> CompareResult {
+    self.compare(t.as_bytes())
+  }
+}
+
+/// Used by the `take_until_either*` family to test a single input element
+/// against a set of alternatives
+///
+/// `Compare` only models prefix equality, not this kind of
+/// "is this element one of a set" membership test, so it gets its own
+/// trait; implement it for a custom input type to reuse
+/// `take_until_either!`/`take_until_either_and_consume!` unchanged over it.
+pub trait FindToken<T> {
+  fn find_token(&self, token: T) -> bool;
+}
+
+impl<'a> FindToken<u8> for &'a [u8] {
+  #[inline(always)]
+  fn find_token(&self, token: u8) -> bool {
+    self.iter().any(|&i| i == token)
+  }
+}
+
+impl<'a> FindToken<u8> for &'a str {
+  #[inline(always)]
+  fn find_token(&self, token: u8) -> bool {
+    self.as_bytes().find_token(token)
+  }
+}